@@ -0,0 +1,52 @@
+use crate::types::exguid::ExGuid;
+use crate::types::object_types::ObjectType;
+use std::fmt;
+
+/// The result type returned by parsing operations in this crate.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors that can occur while parsing a OneNote/OneStore file.
+#[derive(Debug)]
+pub enum Error {
+    /// A structure declared or expected one object type but a different one was found.
+    UnexpectedObjectType {
+        expected: ObjectType,
+        found: ObjectType,
+    },
+    /// A required root object (e.g. the content or metadata root of a section)
+    /// is not present in the object space.
+    MissingRoot {
+        object: &'static str,
+        root: &'static str,
+    },
+    /// A root references an object that does not exist in the object space.
+    MissingObject { object: &'static str, id: ExGuid },
+    /// An object group's on-disk structure does not match any known layout.
+    MalformedObjectGroup(String),
+    /// A change frequency value outside of the known range was encountered.
+    UnexpectedChangeFrequency(u64),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnexpectedObjectType { expected, found } => write!(
+                f,
+                "unexpected object type: expected {:?}, found {:?}",
+                expected, found
+            ),
+            Error::MissingRoot { object, root } => write!(f, "{} has no {} root", object, root),
+            Error::MissingObject { object, id } => {
+                write!(f, "{} object {:?} is missing", object, id)
+            }
+            Error::MalformedObjectGroup(message) => {
+                write!(f, "malformed object group: {}", message)
+            }
+            Error::UnexpectedChangeFrequency(value) => {
+                write!(f, "unexpected change frequency: {}", value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -1,3 +1,4 @@
+use crate::error::{Error, Result};
 use crate::fsshttpb::data_element::value::DataElementValue;
 use crate::types::binary_item::BinaryItem;
 use crate::types::cell_id::CellId;
@@ -6,6 +7,7 @@ use crate::types::exguid::ExGuid;
 use crate::types::object_types::ObjectType;
 use crate::types::stream_object::ObjectHeader;
 use crate::Reader;
+use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Formatter;
 
@@ -14,6 +16,69 @@ pub(crate) struct ObjectGroup {
     pub(crate) declarations: Vec<ObjectGroupDeclaration>,
     pub(crate) metadata: Vec<ObjectGroupMetadata>,
     pub(crate) objects: Vec<ObjectGroupData>,
+    blob_index: HashMap<ExGuid, usize>,
+}
+
+impl ObjectGroup {
+    /// Resolves a blob or object id to its backing bytes. A `BlobReference`
+    /// entry doesn't hold bytes itself, only the id of the declaration that
+    /// does, so this follows that indirection until it lands on an `Object`
+    /// entry (or gives up once every entry has been visited once).
+    pub(crate) fn resolve_blob(&self, blob: ExGuid) -> Option<&[u8]> {
+        let mut id = blob;
+
+        for _ in 0..=self.objects.len() {
+            let index = *self.blob_index.get(&id)?;
+
+            match self.objects.get(index)? {
+                ObjectGroupData::Object { data, .. } => return Some(data.as_slice()),
+                ObjectGroupData::BlobReference { blob, .. } => id = *blob,
+                ObjectGroupData::ObjectExcluded { .. } => return None,
+            }
+        }
+
+        None
+    }
+
+    /// Indexes each declaration by its `object_id`, plus its `blob_id` when
+    /// it declares a blob, so the matching `objects` entry (declarations and
+    /// objects are parsed as parallel, same-order sequences) can be found by
+    /// either id.
+    fn build_blob_index(declarations: &[ObjectGroupDeclaration]) -> HashMap<ExGuid, usize> {
+        declarations
+            .iter()
+            .enumerate()
+            .flat_map(|(index, declaration)| {
+                let mut ids = vec![declaration.object_id()];
+                if let ObjectGroupDeclaration::Blob { blob_id, .. } = declaration {
+                    ids.push(*blob_id);
+                }
+
+                ids.into_iter()
+                    .map(move |id| (id, index))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// `build_blob_index`/`resolve_blob` assume `declarations[i]` and
+    /// `objects[i]` describe the same entry. Nothing in the wire format
+    /// enforces that, so reject the group instead of silently resolving a
+    /// blob to the wrong bytes if the two lists ever drift out of step.
+    fn verify_declarations_match_objects(
+        declarations: &[ObjectGroupDeclaration],
+        objects: &[ObjectGroupData],
+    ) -> Result<()> {
+        if declarations.len() != objects.len() {
+            return Err(Error::MalformedObjectGroup(format!(
+                "object group has {} declarations but {} data entries",
+                declarations.len(),
+                objects.len()
+            )));
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
@@ -65,16 +130,18 @@ pub(crate) enum ObjectChangeFrequency {
 }
 
 impl ObjectChangeFrequency {
-    fn parse(value: u64) -> ObjectChangeFrequency {
+    fn parse(value: u64) -> Result<ObjectChangeFrequency> {
         match value {
-            x if x == ObjectChangeFrequency::Unknown as u64 => ObjectChangeFrequency::Unknown,
-            x if x == ObjectChangeFrequency::Frequent as u64 => ObjectChangeFrequency::Frequent,
-            x if x == ObjectChangeFrequency::Infrequent as u64 => ObjectChangeFrequency::Infrequent,
+            x if x == ObjectChangeFrequency::Unknown as u64 => Ok(ObjectChangeFrequency::Unknown),
+            x if x == ObjectChangeFrequency::Frequent as u64 => Ok(ObjectChangeFrequency::Frequent),
+            x if x == ObjectChangeFrequency::Infrequent as u64 => {
+                Ok(ObjectChangeFrequency::Infrequent)
+            }
             x if x == ObjectChangeFrequency::Independent as u64 => {
-                ObjectChangeFrequency::Independent
+                Ok(ObjectChangeFrequency::Independent)
             }
-            x if x == ObjectChangeFrequency::Custom as u64 => ObjectChangeFrequency::Custom,
-            x => panic!("unexpected change frequency: {}", x),
+            x if x == ObjectChangeFrequency::Custom as u64 => Ok(ObjectChangeFrequency::Custom),
+            x => Err(Error::UnexpectedChangeFrequency(x)),
         }
     }
 }
@@ -135,40 +202,63 @@ impl fmt::Debug for DebugSize {
 }
 
 impl DataElementValue {
-    pub(crate) fn parse_object_group(reader: Reader) -> DataElementValue {
-        let declarations = DataElementValue::parse_object_group_declarations(reader);
+    pub(crate) fn parse_object_group(reader: Reader) -> Result<DataElementValue> {
+        let declarations = DataElementValue::parse_object_group_declarations(reader)?;
 
         let mut metadata = vec![];
 
         let object_header = ObjectHeader::parse(reader);
         match object_header.object_type {
             ObjectType::ObjectGroupMetadataBlock => {
-                metadata = DataElementValue::parse_object_group_metadata(reader);
+                metadata = DataElementValue::parse_object_group_metadata(reader)?;
 
                 // Parse object header for the group data section
                 let object_header = ObjectHeader::parse(reader);
-                assert_eq!(object_header.object_type, ObjectType::ObjectGroupData);
+                if object_header.object_type != ObjectType::ObjectGroupData {
+                    return Err(Error::UnexpectedObjectType {
+                        expected: ObjectType::ObjectGroupData,
+                        found: object_header.object_type,
+                    });
+                }
             }
             ObjectType::ObjectGroupData => {} // Skip, will be parsed below
-            _ => panic!("unexpected object type: 0x{:x}", object_header.object_type),
+            found => {
+                return Err(Error::UnexpectedObjectType {
+                    expected: ObjectType::ObjectGroupData,
+                    found,
+                })
+            }
         }
-        let objects = DataElementValue::parse_object_group_data(reader);
+        let objects = DataElementValue::parse_object_group_data(reader)?;
+
+        let end_type = ObjectHeader::parse_end_8(reader);
+        if end_type != ObjectType::DataElement {
+            return Err(Error::UnexpectedObjectType {
+                expected: ObjectType::DataElement,
+                found: end_type,
+            });
+        }
+
+        ObjectGroup::verify_declarations_match_objects(&declarations, &objects)?;
 
-        assert_eq!(ObjectHeader::parse_end_8(reader), ObjectType::DataElement);
+        let blob_index = ObjectGroup::build_blob_index(&declarations);
 
-        DataElementValue::ObjectGroup(ObjectGroup {
+        Ok(DataElementValue::ObjectGroup(ObjectGroup {
             declarations,
             metadata,
             objects,
-        })
+            blob_index,
+        }))
     }
 
-    fn parse_object_group_declarations(reader: Reader) -> Vec<ObjectGroupDeclaration> {
+    fn parse_object_group_declarations(reader: Reader) -> Result<Vec<ObjectGroupDeclaration>> {
         let object_header = ObjectHeader::parse(reader);
-        assert_eq!(
-            object_header.object_type,
-            ObjectType::ObjectGroupDeclaration
-        );
+        if object_header.object_type != ObjectType::ObjectGroupDeclaration {
+            return Err(Error::UnexpectedObjectType {
+                expected: ObjectType::ObjectGroupDeclaration,
+                found: object_header.object_type,
+            });
+        }
 
         let mut declarations = vec![];
 
@@ -209,14 +299,19 @@ impl DataElementValue {
                         cell_reference_count,
                     })
                 }
-                _ => panic!("unexpected object type: 0x{:x}", object_header.object_type),
+                found => {
+                    return Err(Error::MalformedObjectGroup(format!(
+                        "unexpected object type in group declarations: 0x{:x}",
+                        found
+                    )))
+                }
             }
         }
 
-        declarations
+        Ok(declarations)
     }
 
-    fn parse_object_group_metadata(reader: Reader) -> Vec<ObjectGroupMetadata> {
+    fn parse_object_group_metadata(reader: Reader) -> Result<Vec<ObjectGroupMetadata>> {
         let mut declarations = vec![];
 
         loop {
@@ -226,18 +321,23 @@ impl DataElementValue {
             }
 
             let object_header = ObjectHeader::parse_32(reader);
-            assert_eq!(object_header.object_type, ObjectType::ObjectGroupMetadata);
+            if object_header.object_type != ObjectType::ObjectGroupMetadata {
+                return Err(Error::UnexpectedObjectType {
+                    expected: ObjectType::ObjectGroupMetadata,
+                    found: object_header.object_type,
+                });
+            }
 
             let frequency = CompactU64::parse(reader);
             declarations.push(ObjectGroupMetadata {
-                change_frequency: ObjectChangeFrequency::parse(frequency.value()),
+                change_frequency: ObjectChangeFrequency::parse(frequency.value())?,
             })
         }
 
-        declarations
+        Ok(declarations)
     }
 
-    fn parse_object_group_data(reader: Reader) -> Vec<ObjectGroupData> {
+    fn parse_object_group_data(reader: Reader) -> Result<Vec<ObjectGroupData>> {
         let mut objects = vec![];
 
         loop {
@@ -272,10 +372,185 @@ impl DataElementValue {
                         blob,
                     })
                 }
-                _ => panic!("unexpected object type: 0x{:x}", object_header.object_type),
+                found => {
+                    return Err(Error::MalformedObjectGroup(format!(
+                        "unexpected object type in group data: 0x{:x}",
+                        found
+                    )))
+                }
             }
         }
 
-        objects
+        Ok(objects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Minimal, distinct `ExGuid` values for test fixtures; the byte content
+    // doesn't matter, only that different seeds parse to different guids.
+    fn exguid(seed: u8) -> ExGuid {
+        ExGuid::parse(Reader::new(&[seed; 20]))
+    }
+
+    fn group(
+        declarations: Vec<ObjectGroupDeclaration>,
+        objects: Vec<ObjectGroupData>,
+    ) -> ObjectGroup {
+        let blob_index = ObjectGroup::build_blob_index(&declarations);
+
+        ObjectGroup {
+            declarations,
+            metadata: vec![],
+            objects,
+            blob_index,
+        }
+    }
+
+    #[test]
+    fn resolve_blob_uses_declaration_position_not_group_membership() {
+        let blob_id = exguid(1);
+        let blob_object_id = exguid(2);
+        let decoy_object_id = exguid(3);
+
+        let declarations = vec![
+            ObjectGroupDeclaration::Blob {
+                object_id: blob_object_id,
+                blob_id,
+                partition_id: 0,
+                object_reference_count: 0,
+                cell_reference_count: 0,
+            },
+            ObjectGroupDeclaration::Object {
+                object_id: decoy_object_id,
+                partition_id: 0,
+                data_size: 0,
+                object_reference_count: 0,
+                cell_reference_count: 0,
+            },
+        ];
+
+        // The decoy entry merely *references* the blob's object id in its own
+        // group list. A lookup keyed on `group.contains(blob_object_id)` would
+        // wrongly resolve the blob to this entry's data instead of its own.
+        let objects = vec![
+            ObjectGroupData::Object {
+                group: vec![],
+                cells: vec![],
+                data: vec![1, 2, 3],
+            },
+            ObjectGroupData::Object {
+                group: vec![blob_object_id],
+                cells: vec![],
+                data: vec![9, 9, 9],
+            },
+        ];
+
+        assert_eq!(
+            group(declarations, objects).resolve_blob(blob_id),
+            Some(&[1, 2, 3][..])
+        );
+    }
+
+    #[test]
+    fn resolve_blob_follows_blob_reference_indirection() {
+        let blob_id = exguid(1);
+        let blob_object_id = exguid(2);
+        let target_object_id = exguid(3);
+
+        // This mirrors real files: a `Blob` declaration is typically paired
+        // with a `BlobReference` data entry that merely points (by object id)
+        // at the declaration that actually owns the bytes.
+        let declarations = vec![
+            ObjectGroupDeclaration::Blob {
+                object_id: blob_object_id,
+                blob_id,
+                partition_id: 0,
+                object_reference_count: 0,
+                cell_reference_count: 0,
+            },
+            ObjectGroupDeclaration::Object {
+                object_id: target_object_id,
+                partition_id: 0,
+                data_size: 0,
+                object_reference_count: 0,
+                cell_reference_count: 0,
+            },
+        ];
+
+        let objects = vec![
+            ObjectGroupData::BlobReference {
+                objects: vec![],
+                cells: vec![],
+                blob: target_object_id,
+            },
+            ObjectGroupData::Object {
+                group: vec![],
+                cells: vec![],
+                data: vec![4, 5, 6],
+            },
+        ];
+
+        assert_eq!(
+            group(declarations, objects).resolve_blob(blob_id),
+            Some(&[4, 5, 6][..])
+        );
+    }
+
+    #[test]
+    fn resolve_blob_returns_none_for_excluded_data() {
+        let blob_id = exguid(1);
+        let blob_object_id = exguid(2);
+
+        let declarations = vec![ObjectGroupDeclaration::Blob {
+            object_id: blob_object_id,
+            blob_id,
+            partition_id: 0,
+            object_reference_count: 0,
+            cell_reference_count: 0,
+        }];
+
+        let objects = vec![ObjectGroupData::ObjectExcluded {
+            group: vec![],
+            cells: vec![],
+            size: 42,
+        }];
+
+        assert_eq!(group(declarations, objects).resolve_blob(blob_id), None);
+    }
+
+    #[test]
+    fn resolve_blob_returns_none_instead_of_panicking_on_stale_index() {
+        let blob_id = exguid(1);
+
+        // `blob_index` points one entry past the end of `objects`, as it
+        // would if the two lists had drifted out of step despite
+        // `verify_declarations_match_objects` being bypassed (as this test
+        // helper does by constructing the group directly).
+        let group = ObjectGroup {
+            declarations: vec![],
+            metadata: vec![],
+            objects: vec![],
+            blob_index: [(blob_id, 0)].into_iter().collect(),
+        };
+
+        assert_eq!(group.resolve_blob(blob_id), None);
+    }
+
+    #[test]
+    fn verify_declarations_match_objects_rejects_length_mismatch() {
+        let declarations = vec![ObjectGroupDeclaration::Object {
+            object_id: exguid(1),
+            partition_id: 0,
+            data_size: 0,
+            object_reference_count: 0,
+            cell_reference_count: 0,
+        }];
+
+        let result = ObjectGroup::verify_declarations_match_objects(&declarations, &[]);
+
+        assert!(matches!(result, Err(Error::MalformedObjectGroup(_))));
     }
 }
@@ -0,0 +1,17 @@
+use crate::error::Result;
+use crate::fsshttpb::data_element::object_group::ObjectGroup;
+use crate::Reader;
+
+/// The parsed payload of a single FSSHTTPB data element.
+#[derive(Debug)]
+pub(crate) enum DataElementValue {
+    ObjectGroup(ObjectGroup),
+}
+
+impl DataElementValue {
+    /// Parses a data element's value. Only object group payloads are
+    /// handled: the value is always parsed as an object group.
+    pub(crate) fn parse(reader: Reader) -> Result<DataElementValue> {
+        DataElementValue::parse_object_group(reader)
+    }
+}
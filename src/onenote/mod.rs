@@ -0,0 +1,13 @@
+mod parser;
+
+use parser::section::Section;
+
+/// Walks every page series in a section, rendering each one. A series (and
+/// its pages) is only parsed here, on first visit, rather than up front.
+pub(crate) fn render_section(section: &Section) {
+    for page_series in section.iter_page_series() {
+        render_page_series(&page_series);
+    }
+}
+
+fn render_page_series(_page_series: &parser::page_series::PageSeries) {}
@@ -0,0 +1,16 @@
+use crate::error::Result;
+use crate::onenote::parser::section::{parse_section, Section};
+use crate::onestore::object_space::ObjectSpace;
+use crate::onestore::OneStore;
+
+pub(crate) mod section;
+
+/// Parses every section object space in a notebook into a `Section`. Each
+/// space/store pair is handed to `parse_section` by value since the
+/// resulting `Section` retains both to parse its page series lazily.
+pub(crate) fn parse_sections(spaces: Vec<(ObjectSpace, OneStore)>) -> Result<Vec<Section>> {
+    spaces
+        .into_iter()
+        .map(|(space, store)| parse_section(space, store))
+        .collect()
+}
@@ -1,46 +1,84 @@
+use crate::error::{Error, Result};
 use crate::one::property_set::{section_metadata_node, section_node};
 use crate::onenote::parser::page_series::{parse_page_series, PageSeries};
 use crate::onestore::object_space::ObjectSpace;
 use crate::onestore::OneStore;
+use crate::types::exguid::ExGuid;
 
 #[derive(Debug)]
 pub struct Section {
     pub(crate) display_name: Option<String>,
-    pub(crate) page_series: Vec<PageSeries>,
+    page_series_ids: Vec<ExGuid>,
+    space: ObjectSpace,
+    store: OneStore,
 }
 
-pub(crate) fn parse_section(space: &ObjectSpace, store: &OneStore) -> Section {
-    let metadata = parse_metadata(space);
-    let content = parse_content(space);
+impl Section {
+    /// The number of page series in this section.
+    pub(crate) fn page_series_count(&self) -> usize {
+        self.page_series_ids.len()
+    }
 
-    let display_name = metadata.display_name().map(String::from);
+    /// Parses the page series at `index`, or `None` if out of bounds. Page
+    /// series are only parsed on access so that listing a section's display
+    /// name doesn't require materializing its pages.
+    pub(crate) fn page_series(&self, index: usize) -> Option<PageSeries> {
+        let page_series_id = *self.page_series_ids.get(index)?;
 
-    let page_series = content
-        .page_series()
-        .iter()
-        .map(|page_series_id| parse_page_series(*page_series_id, space, store))
-        .collect();
+        Some(parse_page_series(page_series_id, &self.space, &self.store))
+    }
 
-    Section {
-        display_name,
-        page_series,
+    /// Lazily parses each page series in this section, one at a time.
+    pub(crate) fn iter_page_series(&self) -> impl Iterator<Item = PageSeries> + '_ {
+        self.page_series_ids
+            .iter()
+            .map(move |page_series_id| parse_page_series(*page_series_id, &self.space, &self.store))
     }
 }
 
-fn parse_content(space: &ObjectSpace) -> section_node::Data {
-    let content_root_id = space.content_root().expect("section has no content root");
+/// Takes ownership of `space` and `store` (rather than borrowing them) since
+/// the returned `Section` retains both to parse its page series lazily.
+pub(crate) fn parse_section(space: ObjectSpace, store: OneStore) -> Result<Section> {
+    let metadata = parse_metadata(&space)?;
+    let content = parse_content(&space)?;
+
+    let display_name = metadata.display_name().map(String::from);
+    let page_series_ids = content.page_series().to_vec();
+
+    Ok(Section {
+        display_name,
+        page_series_ids,
+        space,
+        store,
+    })
+}
+
+fn parse_content(space: &ObjectSpace) -> Result<section_node::Data> {
+    let content_root_id = space.content_root().ok_or(Error::MissingRoot {
+        object: "section",
+        root: "content",
+    })?;
     let content_object = space
         .get_object(content_root_id)
-        .expect("section content object is missing");
+        .ok_or(Error::MissingObject {
+            object: "section content",
+            id: content_root_id,
+        })?;
 
-    section_node::parse(content_object)
+    Ok(section_node::parse(content_object))
 }
 
-fn parse_metadata(space: &ObjectSpace) -> section_metadata_node::Data {
-    let metadata_root_id = space.metadata_root().expect("section has no metadata root");
+fn parse_metadata(space: &ObjectSpace) -> Result<section_metadata_node::Data> {
+    let metadata_root_id = space.metadata_root().ok_or(Error::MissingRoot {
+        object: "section",
+        root: "metadata",
+    })?;
     let metadata_object = space
         .get_object(metadata_root_id)
-        .expect("section metadata object is missing");
+        .ok_or(Error::MissingObject {
+            object: "section metadata",
+            id: metadata_root_id,
+        })?;
 
-    section_metadata_node::parse(metadata_object)
+    Ok(section_metadata_node::parse(metadata_object))
 }